@@ -1,3 +1,5 @@
+use std::path::PathBuf;
+
 use clap::{Parser, Subcommand, AppSettings};
 
 #[derive(Parser)]
@@ -7,6 +9,10 @@ use clap::{Parser, Subcommand, AppSettings};
 pub struct Cli {
     #[clap(subcommand)]
     pub command: Commands,
+
+    /// Path to the config file (default: ~/.config/awsconnect/config.toml)
+    #[clap(long, global = true)]
+    pub config: Option<PathBuf>,
 }
 
 #[derive(Subcommand)]
@@ -30,12 +36,179 @@ pub enum Commands {
         /// Name of the cluster to connect to
         #[clap(short, long)]
         cluster: Option<String>,
-    
+
+        /// Search for the task across every cluster the profile can access, instead of just `cluster`.
+        /// This is the default when `cluster` isn't given.
+        #[clap(long)]
+        all_clusters: bool,
+
         /// Name of the region to connect to
         #[clap(short, long)]
         region: Option<String>,
-    
+
+        /// Resolve credentials via `aws-vault exec` instead of natively assuming the profile's role_arn
+        #[clap(long)]
+        use_aws_vault: bool,
+
+        /// One-shot command to run instead of dropping into an interactive shell
+        #[clap(long)]
+        command: Option<String>,
+
+        /// Whether to run interactively (pass `--interactive false` for non-interactive one-shot commands)
+        #[clap(long, parse(try_from_str), default_value_t = true)]
+        interactive: bool,
+
         // The ECS task to connect to
         task: Option<String>
+    },
+    /// Tail CloudWatch Logs for a container in an ECS task
+    Logs {
+        /// Name of the environment to connect to (or profile - to use!)
+        #[clap(short, long, alias = "profile", short_alias = 'p')]
+        environment: Option<String>,
+
+        /// Name of the container to connect to
+        #[clap(long, visible_alias = "con")]
+        container: Option<String>,
+
+        /// Name of the cluster to connect to
+        #[clap(short, long)]
+        cluster: Option<String>,
+
+        /// Search for the task across every cluster the profile can access, instead of just `cluster`.
+        /// This is the default when `cluster` isn't given.
+        #[clap(long)]
+        all_clusters: bool,
+
+        /// Name of the region to connect to
+        #[clap(short, long)]
+        region: Option<String>,
+
+        /// Resolve credentials via `aws-vault exec` instead of natively assuming the profile's role_arn
+        #[clap(long)]
+        use_aws_vault: bool,
+
+        /// Keep polling for new log events instead of exiting after the first page
+        #[clap(short, long)]
+        follow: bool,
+
+        /// Only show events at or after this time (RFC 3339, e.g. 2023-01-01T00:00:00Z)
+        #[clap(long, alias = "start-time")]
+        since: Option<String>,
+
+        // The ECS task to read logs from
+        task: Option<String>
+    },
+    /// Inspect and manage ECS task definitions
+    TaskDefinitions {
+        #[clap(subcommand)]
+        command: TaskDefinitionCommands,
+    },
+    /// View or set per-environment defaults
+    Config {
+        #[clap(subcommand)]
+        command: ConfigCommands,
     }
 }
+
+#[derive(Subcommand)]
+pub enum ConfigCommands {
+    /// Print the stored defaults for an environment
+    Show {
+        environment: String,
+    },
+    /// Write stored defaults for an environment without needing a live `execute`
+    Init {
+        environment: String,
+
+        /// Default cluster ARN to store
+        #[clap(long)]
+        cluster: Option<String>,
+
+        /// Default container name to store
+        #[clap(long)]
+        container: Option<String>,
+
+        /// Default region to store
+        #[clap(long)]
+        region: Option<String>,
+
+        /// Default one-shot command to store
+        #[clap(long)]
+        command: Option<String>,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum TaskDefinitionCommands {
+    /// List task definitions
+    List {
+        /// Name of the environment to connect to (or profile - to use!)
+        #[clap(short, long, alias = "profile", short_alias = 'p')]
+        environment: Option<String>,
+
+        /// Name of the region to connect to
+        #[clap(short, long)]
+        region: Option<String>,
+
+        /// Resolve credentials via `aws-vault exec` instead of natively assuming the profile's role_arn
+        #[clap(long)]
+        use_aws_vault: bool,
+
+        /// Only list task definitions whose family starts with this prefix
+        #[clap(long)]
+        family: Option<String>,
+
+        /// List INACTIVE task definitions instead of ACTIVE ones
+        #[clap(long)]
+        inactive: bool,
+
+        /// Print the full task definition ARN instead of just family:revision
+        #[clap(long)]
+        full_arn: bool,
+    },
+    /// Describe a task definition
+    Describe {
+        /// Name of the environment to connect to (or profile - to use!)
+        #[clap(short, long, alias = "profile", short_alias = 'p')]
+        environment: Option<String>,
+
+        /// Name of the region to connect to
+        #[clap(short, long)]
+        region: Option<String>,
+
+        /// Resolve credentials via `aws-vault exec` instead of natively assuming the profile's role_arn
+        #[clap(long)]
+        use_aws_vault: bool,
+
+        /// Output format
+        #[clap(long, arg_enum, default_value = "yaml")]
+        format: OutputFormat,
+
+        /// Family, family:revision, or full ARN of the task definition to describe
+        task_definition: Option<String>,
+    },
+    /// Deregister a task definition
+    Deregister {
+        /// Name of the environment to connect to (or profile - to use!)
+        #[clap(short, long, alias = "profile", short_alias = 'p')]
+        environment: Option<String>,
+
+        /// Name of the region to connect to
+        #[clap(short, long)]
+        region: Option<String>,
+
+        /// Resolve credentials via `aws-vault exec` instead of natively assuming the profile's role_arn
+        #[clap(long)]
+        use_aws_vault: bool,
+
+        /// Family, family:revision, or full ARN of the task definition to deregister
+        task_definition: Option<String>,
+    },
+}
+
+#[derive(clap::ArgEnum, Clone, Debug)]
+pub enum OutputFormat {
+    Yaml,
+    Json,
+}
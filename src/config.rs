@@ -0,0 +1,57 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+/// Per-environment defaults loaded from `~/.config/awsconnect/config.toml` (or `--config`), used to
+/// skip the cluster/container/region prompts once a user has already picked them for an environment.
+#[derive(Debug, Default, Deserialize, Serialize)]
+pub struct Config {
+    #[serde(default)]
+    pub environments: HashMap<String, EnvironmentDefaults>,
+}
+
+#[derive(Debug, Default, Clone, Deserialize, Serialize)]
+pub struct EnvironmentDefaults {
+    pub cluster: Option<String>,
+    pub container: Option<String>,
+    pub region: Option<String>,
+    pub command: Option<String>,
+}
+
+impl Config {
+    pub fn defaults_for(&self, environment: &str) -> Option<&EnvironmentDefaults> {
+        self.environments.get(environment)
+    }
+}
+
+/// Default config path: `~/.config/awsconnect/config.toml`.
+pub fn default_path() -> Result<PathBuf> {
+    let config_dir = dirs::config_dir().context("Couldn't determine the user's config directory")?;
+    Ok(config_dir.join("awsconnect").join("config.toml"))
+}
+
+/// Loads the config file, returning an empty `Config` if it doesn't exist yet.
+pub fn load(path: &Path) -> Result<Config> {
+    if !path.exists() {
+        return Ok(Config::default());
+    }
+    let contents = fs::read_to_string(path).with_context(|| format!("Failed to read config file at {}", path.display()))?;
+    toml::from_str(&contents).with_context(|| format!("Failed to parse config file at {}", path.display()))
+}
+
+/// Writes `defaults` back into the config file for `environment`, creating the file and its parent
+/// directory if needed, and preserving any other environments' entries already on disk.
+pub fn save_defaults(path: &Path, environment: &str, defaults: EnvironmentDefaults) -> Result<()> {
+    let mut config = load(path)?;
+    config.environments.insert(environment.to_owned(), defaults);
+
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).with_context(|| format!("Failed to create config directory at {}", parent.display()))?;
+    }
+
+    let serialized = toml::to_string_pretty(&config).context("Failed to serialize config")?;
+    fs::write(path, serialized).with_context(|| format!("Failed to write config file at {}", path.display()))
+}
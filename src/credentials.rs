@@ -0,0 +1,130 @@
+use std::str::FromStr;
+
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use aws_config::profile::{Profile, ProfileSet};
+use dialoguer::{theme::ColorfulTheme, Input};
+use rusoto_core::{HttpClient, Region};
+use rusoto_credential::{
+    AutoRefreshingProvider, AwsCredentials, ChainProvider, CredentialsError, ProvideAwsCredentials,
+    StaticProvider,
+};
+use rusoto_sts::{AssumeRoleRequest, Sts, StsClient};
+
+/// Resolves a native, in-process credentials provider for `profile`: an `AutoRefreshingProvider`
+/// wrapping an `sts:AssumeRole` call for `profile`'s `role_arn`, built on top of a base provider for
+/// `source_profile` (static keys if present, otherwise the default credential chain).
+///
+/// This replaces shelling out to `aws-vault` and scraping its `env` output; see `--use-aws-vault`
+/// for the fallback path when that in-process resolution doesn't fit (e.g. SSO profiles).
+pub fn build_provider(profile_set: &ProfileSet, profile: &Profile) -> Result<AutoRefreshingProvider<AssumeRoleProvider>> {
+    let role_arn = profile
+        .get("role_arn")
+        .context("Profile has no role_arn; native credential resolution requires a role-chaining profile")?
+        .to_owned();
+    let mfa_serial = profile.get("mfa_serial").map(str::to_owned);
+    let external_id = profile.get("external_id").map(str::to_owned);
+    let region = match profile.get("region") {
+        Some(region) => Region::from_str(region)?,
+        None => Region::default(),
+    };
+
+    let source_profile = resolve_source_profile(profile_set, profile);
+    let sts_http_client = HttpClient::new().context("Failed to create HTTP client for STS")?;
+    let sts_client = StsClient::new_with(sts_http_client, base_provider(source_profile), region);
+
+    let provider = AssumeRoleProvider {
+        sts_client,
+        role_arn,
+        session_name: format!("awsconnect-{}", profile.name()),
+        mfa_serial,
+        external_id,
+    };
+
+    AutoRefreshingProvider::new(provider).context("Failed to set up auto-refreshing STS credentials provider")
+}
+
+/// Builds the base provider used to authenticate the `sts:AssumeRole` call itself: static keys from
+/// `source_profile` when present, otherwise the default AWS credential chain (env vars, shared
+/// credentials file, instance/container metadata).
+fn base_provider(source_profile: &Profile) -> Box<dyn ProvideAwsCredentials + Send + Sync> {
+    match (
+        source_profile.get("aws_access_key_id"),
+        source_profile.get("aws_secret_access_key"),
+    ) {
+        (Some(key), Some(secret)) => {
+            Box::new(StaticProvider::new_minimal(key.to_owned(), secret.to_owned()))
+        }
+        _ => Box::new(ChainProvider::new()),
+    }
+}
+
+/// Looks up the profile that should authenticate the assume-role call: `profile`'s `source_profile`
+/// if set and present in `profile_set`, otherwise `profile` itself.
+fn resolve_source_profile<'a>(profile_set: &'a ProfileSet, profile: &'a Profile) -> &'a Profile {
+    profile
+        .get("source_profile")
+        .and_then(|name| profile_set.get_profile(name))
+        .unwrap_or(profile)
+}
+
+/// A `ProvideAwsCredentials` implementation that calls `sts:AssumeRole` on every refresh, prompting
+/// interactively for an MFA token code when the profile has an `mfa_serial` configured.
+pub struct AssumeRoleProvider {
+    sts_client: StsClient,
+    role_arn: String,
+    session_name: String,
+    mfa_serial: Option<String>,
+    external_id: Option<String>,
+}
+
+#[async_trait]
+impl ProvideAwsCredentials for AssumeRoleProvider {
+    async fn credentials(&self) -> Result<AwsCredentials, CredentialsError> {
+        let token_code = match &self.mfa_serial {
+            Some(_) => Some(
+                prompt_for_mfa_code()
+                    .map_err(|err| CredentialsError::new(err.to_string()))?,
+            ),
+            None => None,
+        };
+
+        let request = AssumeRoleRequest {
+            role_arn: self.role_arn.clone(),
+            role_session_name: self.session_name.clone(),
+            serial_number: self.mfa_serial.clone(),
+            token_code,
+            external_id: self.external_id.clone(),
+            ..AssumeRoleRequest::default()
+        };
+
+        let response = self
+            .sts_client
+            .assume_role(request)
+            .await
+            .map_err(|err| CredentialsError::new(err.to_string()))?;
+
+        let credentials = response
+            .credentials
+            .ok_or_else(|| CredentialsError::new("AssumeRole response had no credentials"))?;
+
+        Ok(AwsCredentials::new(
+            credentials.access_key_id,
+            credentials.secret_access_key,
+            Some(credentials.session_token),
+            Some(
+                credentials
+                    .expiration
+                    .parse()
+                    .map_err(|_| CredentialsError::new("Failed to parse session expiration"))?,
+            ),
+        ))
+    }
+}
+
+fn prompt_for_mfa_code() -> Result<String> {
+    Input::with_theme(&ColorfulTheme::default())
+        .with_prompt("Enter MFA code")
+        .interact_text()
+        .context("Failed to read MFA code")
+}
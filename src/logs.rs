@@ -0,0 +1,114 @@
+use std::time::Duration;
+
+use anyhow::{bail, Context, Result};
+use rusoto_ecs::{DescribeTaskDefinitionRequest, Ecs, EcsClient};
+use rusoto_logs::{CloudWatchLogs, CloudWatchLogsClient, FilterLogEventsRequest};
+
+use crate::task::{Container, Task};
+
+const POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Where a container's output lives in CloudWatch Logs, resolved from its task definition.
+pub struct LogStream {
+    pub group: String,
+    pub stream: String,
+    /// The region the `awslogs` driver is configured to ship to — not necessarily the region the
+    /// ECS call itself targeted, so the CloudWatch Logs client must be built from this, not reused.
+    pub region: String,
+}
+
+/// Resolves the CloudWatch Logs group/stream for `container` by reading its task definition's
+/// `awslogs` log configuration. Only the `awslogs` driver is supported; anything else is reported
+/// as an error since there's nowhere to tail from.
+pub async fn resolve_log_stream(client: &EcsClient, task: &Task, container: &Container) -> Result<LogStream> {
+    let request = DescribeTaskDefinitionRequest {
+        task_definition: task.task_definition_arn.clone(),
+        ..DescribeTaskDefinitionRequest::default()
+    };
+    let response = client.describe_task_definition(request).await.context("Failed to describe task definition")?;
+    let task_definition = response.task_definition.context("No task definition returned")?;
+    let container_definitions = task_definition.container_definitions.context("Task definition has no container definitions")?;
+    let container_definition = container_definitions
+        .into_iter()
+        .find(|c| c.name.as_deref() == Some(container.name.as_str()))
+        .with_context(|| format!("No container definition found matching '{}'", container.name))?;
+
+    let log_configuration = container_definition
+        .log_configuration
+        .with_context(|| format!("Container '{}' has no log_configuration", container.name))?;
+
+    if log_configuration.log_driver != "awslogs" {
+        bail!("Container '{}' uses the '{}' log driver; only 'awslogs' is supported", container.name, log_configuration.log_driver);
+    }
+
+    let options = log_configuration.options.unwrap_or_default();
+    let group = options.get("awslogs-group").cloned().context("Log configuration is missing awslogs-group")?;
+    let prefix = options.get("awslogs-stream-prefix").cloned().context("Log configuration is missing awslogs-stream-prefix")?;
+    let region = options.get("awslogs-region").cloned().context("Log configuration is missing awslogs-region")?;
+
+    let stream = stream_name(&prefix, &container.name, &task.arn)?;
+
+    Ok(LogStream { group, stream, region })
+}
+
+/// Builds the `<prefix>/<container>/<task-id>` stream name `awslogs` derives for a container,
+/// extracting the task id as the last `/`-separated segment of the task ARN.
+fn stream_name(prefix: &str, container_name: &str, task_arn: &str) -> Result<String> {
+    let task_id = task_arn.rsplit('/').next().context("Couldn't extract task id from task ARN")?;
+    Ok(format!("{}/{}/{}", prefix, container_name, task_id))
+}
+
+/// Prints CloudWatch Logs events for `log_stream`, starting at `start_time` (epoch millis) if given.
+/// When `follow` is set, keeps polling until interrupted, with one millisecond after the last event's
+/// timestamp as the new start time.
+pub async fn tail(client: &CloudWatchLogsClient, log_stream: &LogStream, follow: bool, start_time: Option<i64>) -> Result<()> {
+    let mut next_token = None;
+    let mut start_time = start_time;
+
+    loop {
+        let request = FilterLogEventsRequest {
+            log_group_name: log_stream.group.clone(),
+            log_stream_names: Some(vec![log_stream.stream.clone()]),
+            start_time,
+            next_token: next_token.clone(),
+            ..FilterLogEventsRequest::default()
+        };
+
+        let response = client.filter_log_events(request).await.context("Failed to fetch CloudWatch Logs events")?;
+
+        for event in response.events.unwrap_or_default() {
+            println!("{}", event.message.unwrap_or_default());
+            start_time = event.timestamp.map(|timestamp| timestamp + 1).or(start_time);
+        }
+
+        next_token = response.next_token;
+
+        if !follow {
+            break;
+        }
+
+        if next_token.is_none() {
+            tokio::time::sleep(POLL_INTERVAL).await;
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stream_name_joins_prefix_container_and_task_id() {
+        let arn = "arn:aws:ecs:us-east-1:123456789012:task/my-cluster/abcdef1234567890";
+        let name = stream_name("prefix", "web", arn).unwrap();
+        assert_eq!(name, "prefix/web/abcdef1234567890");
+    }
+
+    #[test]
+    fn stream_name_uses_final_slash_separated_segment_as_task_id() {
+        let name = stream_name("prefix", "web", "arn:aws:ecs:us-east-1:123456789012:task/cluster/abc/def").unwrap();
+        assert_eq!(name, "prefix/web/def");
+    }
+}
@@ -3,22 +3,30 @@
 #![allow(clippy::single_match_else)]
 
 use std::str::FromStr;
+use std::process::ExitStatus;
 use std::{io::Read, env};
 
 use log::{warn, debug};
 use anyhow::{Result, Context, bail, anyhow};
 use clap::StructOpt;
 use dialoguer::{theme::ColorfulTheme, Select};
-use aws_config::{profile::{Profile, load}};
+use aws_config::profile::{Profile, ProfileSet, load};
 use aws_types::os_shim_internal::{Env, Fs};
-use rusoto_core::Region;
+use futures::future::join_all;
+use rusoto_core::{HttpClient, Region};
+use rusoto_credential::ProvideAwsCredentials;
 use rusoto_ecs::{Ecs, EcsClient, ListClustersRequest, ListTasksRequest, DescribeTasksRequest};
+use rusoto_logs::CloudWatchLogsClient;
 use subprocess::Exec;
 use dotenv_parser::parse_dotenv;
 use which::which;
 
 mod cli;
+mod config;
+mod credentials;
+mod logs;
 mod task;
+mod task_definitions;
 
 use crate::task::Container;
 use crate::cli::Cli;
@@ -28,36 +36,151 @@ use crate::task::Task;
 async fn main() -> Result<()> {
     env_logger::init();
     let cli = Cli::parse();
-    confirm_dependencies()?;
+    confirm_dependencies(&cli.command)?;
+
+    let config_path = match cli.config {
+        Some(path) => path,
+        None => config::default_path()?,
+    };
 
     match cli.command {
         cli::Commands::Login {environment} => {
-            let profile = get_profile(environment).await?;
+            let (_, profile) = get_profile(environment).await?;
             Exec::shell(format!("aws-vault login {}", profile.name())).join()?;
         }
-        
-        cli::Commands::Execute { environment, container, cluster, region, task } => {
-            let profile = get_profile(environment).await?;
-            setup_environment(&profile)?;
+
+        cli::Commands::Execute { environment, container, cluster, all_clusters, region, task, use_aws_vault, command, interactive } => {
+            let (profile_set, profile) = get_profile(environment).await?;
+            let defaults = config::load(&config_path)?.defaults_for(profile.name()).cloned();
+
+            let cluster = cluster.or_else(|| defaults.as_ref().and_then(|d| d.cluster.clone()));
+            let container = container.or_else(|| defaults.as_ref().and_then(|d| d.container.clone()));
+            let region = region.or_else(|| defaults.as_ref().and_then(|d| d.region.clone()));
+            let command = command.or_else(|| defaults.as_ref().and_then(|d| d.command.clone()));
 
             let region = match region {
                 Some(r) => Region::from_str(&r)?,
                 None => Region::default(),
             };
-            
-            let ecs_client = EcsClient::new(region);
-            let cluster_arn = get_cluster(cluster, &ecs_client).await?;
-            let task = get_tasks(task, &cluster_arn, &ecs_client).await?;
+
+            let ecs_client = build_ecs_client(&profile_set, &profile, region.clone(), use_aws_vault).await?;
+            let task = get_tasks(task, cluster, all_clusters, &ecs_client).await?;
+            let container = choose_container(&task, container)?;
+            let status = execute_in_container(&task.cluster_arn, &task, &container, command.as_deref(), interactive)?;
+
+            if status.success() {
+                // Only cluster/container/region are learned from a run; `command` is transient for
+                // one-shot invocations and must be set explicitly via `config init` to persist.
+                config::save_defaults(&config_path, profile.name(), config::EnvironmentDefaults {
+                    cluster: Some(task.cluster_arn.clone()),
+                    container: Some(container.name),
+                    region: Some(region.name().to_owned()),
+                    command: defaults.as_ref().and_then(|d| d.command.clone()),
+                })?;
+            }
+
+            if command.is_some() && !interactive {
+                std::process::exit(status.code().unwrap_or(1));
+            }
+        },
+
+        cli::Commands::Logs { environment, container, cluster, all_clusters, region, use_aws_vault, follow, since, task } => {
+            let (profile_set, profile) = get_profile(environment).await?;
+
+            let region = match region {
+                Some(r) => Region::from_str(&r)?,
+                None => Region::default(),
+            };
+
+            let ecs_client = build_ecs_client(&profile_set, &profile, region, use_aws_vault).await?;
+            let task = get_tasks(task, cluster, all_clusters, &ecs_client).await?;
             let container = choose_container(&task, container)?;
-            execute_bash_container(&cluster_arn, &task, &container)?;
+
+            let log_stream = logs::resolve_log_stream(&ecs_client, &task, &container).await?;
+            let start_time = since.map(|since| parse_start_time(&since)).transpose()?;
+            let logs_region = Region::from_str(&log_stream.region)?;
+            let logs_client = build_logs_client(&profile_set, &profile, logs_region, use_aws_vault).await?;
+            logs::tail(&logs_client, &log_stream, follow, start_time).await?;
+        },
+
+        cli::Commands::TaskDefinitions { command } => match command {
+            cli::TaskDefinitionCommands::List { environment, region, use_aws_vault, family, inactive, full_arn } => {
+                let (profile_set, profile) = get_profile(environment).await?;
+                let region = match region {
+                    Some(r) => Region::from_str(&r)?,
+                    None => Region::default(),
+                };
+
+                let ecs_client = build_ecs_client(&profile_set, &profile, region, use_aws_vault).await?;
+                let arns = task_definitions::list(&ecs_client, family, inactive).await?;
+                for arn in &arns {
+                    if full_arn {
+                        println!("{}", arn);
+                    } else {
+                        println!("{}", task_definitions::friendly_name(arn));
+                    }
+                }
+            },
+
+            cli::TaskDefinitionCommands::Describe { environment, region, use_aws_vault, format, task_definition } => {
+                let (profile_set, profile) = get_profile(environment).await?;
+                let region = match region {
+                    Some(r) => Region::from_str(&r)?,
+                    None => Region::default(),
+                };
+
+                let ecs_client = build_ecs_client(&profile_set, &profile, region, use_aws_vault).await?;
+                let task_definition = task_definitions::choose_task_definition(&ecs_client, task_definition).await?;
+                println!("{}", task_definitions::describe(&ecs_client, &task_definition, &format).await?);
+            },
+
+            cli::TaskDefinitionCommands::Deregister { environment, region, use_aws_vault, task_definition } => {
+                let (profile_set, profile) = get_profile(environment).await?;
+                let region = match region {
+                    Some(r) => Region::from_str(&r)?,
+                    None => Region::default(),
+                };
+
+                let ecs_client = build_ecs_client(&profile_set, &profile, region, use_aws_vault).await?;
+                let task_definition = task_definitions::choose_task_definition(&ecs_client, task_definition).await?;
+                task_definitions::deregister(&ecs_client, &task_definition).await?;
+            },
+        },
+
+        cli::Commands::Config { command } => match command {
+            cli::ConfigCommands::Show { environment } => {
+                let config = config::load(&config_path)?;
+                match config.defaults_for(&environment) {
+                    Some(defaults) => println!("{:#?}", defaults),
+                    None => println!("No defaults stored for '{}'", environment),
+                }
+            },
+
+            cli::ConfigCommands::Init { environment, cluster, container, region, command } => {
+                let existing = config::load(&config_path)?.defaults_for(&environment).cloned();
+
+                config::save_defaults(&config_path, &environment, config::EnvironmentDefaults {
+                    cluster: cluster.or_else(|| existing.as_ref().and_then(|d| d.cluster.clone())),
+                    container: container.or_else(|| existing.as_ref().and_then(|d| d.container.clone())),
+                    region: region.or_else(|| existing.as_ref().and_then(|d| d.region.clone())),
+                    command: command.or_else(|| existing.as_ref().and_then(|d| d.command.clone())),
+                })?;
+            },
         },
     }
 
     Ok(())
 }
 
-/// Extracts the needed environment variables to call AWS commands from aws-vault, and adds them to the current process
-fn setup_environment(profile: &Profile) -> Result<()> {
+/// Parses a `--since`/`--start-time` value (RFC 3339) into epoch milliseconds for `FilterLogEvents`
+fn parse_start_time(since: &str) -> Result<i64> {
+    let parsed = chrono::DateTime::parse_from_rfc3339(since).context("Failed to parse --since as an RFC 3339 timestamp")?;
+    Ok(parsed.timestamp_millis())
+}
+
+/// Extracts the needed environment variables to call AWS commands from aws-vault, and adds them to the current process.
+/// Fallback path for `--use-aws-vault`; see `credentials::build_provider` for the native equivalent.
+fn setup_environment_via_aws_vault(profile: &Profile) -> Result<()> {
     let mut output = Exec::shell(format!("aws-vault exec {} -- env | grep AWS_", profile.name())).stream_stdout()?;
     let mut buffer = String::new();
     output.read_to_string(&mut buffer)?;
@@ -68,9 +191,45 @@ fn setup_environment(profile: &Profile) -> Result<()> {
     Ok(())
 }
 
+/// Builds the ECS client, either by natively assuming the profile's `role_arn` in-process or, when
+/// `use_aws_vault` is set, by shelling out to `aws-vault exec` and reading its exported environment.
+async fn build_ecs_client(profile_set: &ProfileSet, profile: &Profile, region: Region, use_aws_vault: bool) -> Result<EcsClient> {
+    if use_aws_vault {
+        setup_environment_via_aws_vault(profile)?;
+        return Ok(EcsClient::new(region));
+    }
+
+    let provider = build_native_provider(profile_set, profile).await?;
+    let ecs_http_client = HttpClient::new().context("Failed to create HTTP client for ECS")?;
+    Ok(EcsClient::new_with(ecs_http_client, provider, region))
+}
+
+/// Builds the CloudWatch Logs client the same way as `build_ecs_client`: natively assuming the
+/// profile's `role_arn` in-process, or, when `use_aws_vault` is set, relying on the environment
+/// `setup_environment_via_aws_vault` already exported for the ECS client built earlier in the same
+/// command. Without this, log reads would fall back to ambient/default credentials instead of the
+/// profile's assumed role, which can silently query the wrong account.
+async fn build_logs_client(profile_set: &ProfileSet, profile: &Profile, region: Region, use_aws_vault: bool) -> Result<CloudWatchLogsClient> {
+    if use_aws_vault {
+        return Ok(CloudWatchLogsClient::new(region));
+    }
+
+    let provider = build_native_provider(profile_set, profile).await?;
+    let logs_http_client = HttpClient::new().context("Failed to create HTTP client for CloudWatch Logs")?;
+    Ok(CloudWatchLogsClient::new_with(logs_http_client, provider, region))
+}
+
+/// Resolves the native assume-role credentials provider for `profile`, failing fast if the role
+/// can't be assumed rather than surfacing an opaque error from the first API call made with it.
+async fn build_native_provider(profile_set: &ProfileSet, profile: &Profile) -> Result<rusoto_credential::AutoRefreshingProvider<credentials::AssumeRoleProvider>> {
+    let provider = credentials::build_provider(profile_set, profile)?;
+    provider.credentials().await.context("Failed to assume profile's role_arn")?;
+    Ok(provider)
+}
+
 /// Selects the current profile to use
-async fn get_profile(passed_profile_name: Option<String>) -> Result<Profile> {
-    let profile = load(&Fs::default(), &Env::default()).await?;
+async fn get_profile(passed_profile_name: Option<String>) -> Result<(ProfileSet, Profile)> {
+    let profile_set = load(&Fs::default(), &Env::default()).await?;
     debug!("Loaded AWS profiles");
     let profile_name = match passed_profile_name {
         Some(profile_name) => {
@@ -78,7 +237,7 @@ async fn get_profile(passed_profile_name: Option<String>) -> Result<Profile> {
             profile_name
         } ,
         None => {
-            let mut options = profile.profiles().filter(|p| *p != "default").collect::<Vec<_>>();
+            let mut options = profile_set.profiles().filter(|p| *p != "default").collect::<Vec<_>>();
             options.sort_unstable();
 
             let selection = Select::with_theme(&ColorfulTheme::default())
@@ -93,49 +252,80 @@ async fn get_profile(passed_profile_name: Option<String>) -> Result<Profile> {
             profile_name
         }
     };
-    
-    let profile = profile.get_profile(&profile_name).context("Couldn't find profile")?;
-    Ok(profile.clone())
+
+    let profile = profile_set.get_profile(&profile_name).context("Couldn't find profile")?.clone();
+    Ok((profile_set, profile))
 }
 
-async fn get_cluster(cluster_name: Option<String>, client: &EcsClient) -> Result<String> {
-    match cluster_name {
-        Some(name) => Ok(name),
-        None => {
-            let result = client.list_clusters(ListClustersRequest::default()).await?;
-            let mut clusters = result.cluster_arns.context("No clusters found")?;
-            clusters.sort();
-            let friendly_cluster_names: Vec<String> = clusters.iter().map(|name| name.clone().split(":cluster/").nth(1).unwrap().to_owned()).collect();
-            let selection = Select::with_theme(&ColorfulTheme::default())
-                .with_prompt("Pick your cluster")
-                .default(0)
-                .items(&friendly_cluster_names[..])
-                .interact()
-                .unwrap();
-            
-            Ok(clusters[selection].clone())
+/// Lists the ARNs of every ECS cluster the profile can access.
+async fn list_cluster_arns(client: &EcsClient) -> Result<Vec<String>> {
+    let mut clusters = Vec::new();
+    let mut next_token = None;
+
+    loop {
+        let request = ListClustersRequest { next_token, ..ListClustersRequest::default() };
+        let result = client.list_clusters(request).await.context("Failed to contact ECS API and list clusters")?;
+        clusters.extend(result.cluster_arns.context("No clusters found")?);
+
+        next_token = result.next_token;
+        if next_token.is_none() {
+            break;
+        }
+    }
+
+    clusters.sort();
+    Ok(clusters)
+}
+
+/// Gets all the running tasks across clusters the profile can access.
+///
+/// When `cluster` is given and `all_clusters` isn't set, only that cluster is queried (as before).
+/// Otherwise every cluster the profile can access is fanned out to concurrently, and the results
+/// are merged into one sorted `Select` list prefixed with each task's owning cluster name.
+async fn get_tasks(task: Option<String>, cluster: Option<String>, all_clusters: bool, client: &EcsClient) -> Result<Task> {
+    if !all_clusters {
+        if let Some(cluster) = cluster {
+            let tasks = list_tasks_in_cluster(task, &cluster, client).await?;
+            return select_task(tasks);
+        }
+    }
+
+    let clusters = list_cluster_arns(client).await?;
+    let lookups = clusters.iter().map(|cluster| list_tasks_in_cluster(task.clone(), cluster, client));
+    let results = join_all(lookups).await;
+
+    let mut tasks = Vec::new();
+    for (cluster, result) in clusters.iter().zip(results) {
+        match result {
+            Ok(found) => tasks.extend(found),
+            Err(err) => debug!("Skipping cluster '{cluster}' that failed to list tasks: {err:#}"),
         }
     }
+
+    select_task(tasks)
 }
 
-/// Gets all the running tasks across clusters the profile can access
-async fn get_tasks(task: Option<String>, cluster: &str, client: &EcsClient) -> Result<Task> {
+/// Lists the tasks matching `task` (or all running tasks if `None`) in a single cluster.
+async fn list_tasks_in_cluster(task: Option<String>, cluster: &str, client: &EcsClient) -> Result<Vec<Task>> {
     match task {
         Some(name) => {
             let describe_request = DescribeTasksRequest { cluster: Some(String::from(cluster)), tasks: vec![name], ..DescribeTasksRequest::default() };
-       
+
             let describe_result = client.describe_tasks(describe_request).await.context("Failed to contact ECS API and describe tasks")?;
             if describe_result.failures.as_ref().is_some() && !describe_result.failures.as_ref().unwrap().is_empty() {
                 bail!("Failed to contact ESC API. Failed: {:?}", describe_result.failures.as_ref().unwrap());
             }
-            let tasks = describe_result.tasks.context("No task found")?;
-            Ok(Task::from( tasks.first().unwrap().clone()))
+            let tasks = describe_result.tasks.unwrap_or_default();
+            Ok(tasks.into_iter().map(Task::from).collect())
         },
         None => {
             let list_request = ListTasksRequest { cluster: Some(String::from(cluster)), ..ListTasksRequest::default() };
 
             let list_result = client.list_tasks(list_request).await.context("Failed to contact ECS API and list tasks")?;
-            let task_arns = list_result.task_arns.context("No tasks found")?;
+            let task_arns = list_result.task_arns.unwrap_or_default();
+            if task_arns.is_empty() {
+                return Ok(Vec::new());
+            }
 
             let describe_request = DescribeTasksRequest { cluster: Some(String::from(cluster)), tasks: task_arns, ..DescribeTasksRequest::default()};
             let describe_result = client.describe_tasks(describe_request).await.context("Failed to contact ECS API and describe tasks")?;
@@ -144,25 +334,35 @@ async fn get_tasks(task: Option<String>, cluster: &str, client: &EcsClient) -> R
                 bail!("Failed to contact ESC API. Failed: {:?}", describe_result.failures.as_ref().unwrap());
             }
 
-            let tasks = describe_result.tasks.context("No tasks found")?;
-            let mut tasks: Vec<Task> = tasks.into_iter().map(Task::from).collect();
-            tasks.sort();
-            let friendly_task_names: Vec<String> = tasks.iter().map(task::Task::friendly_output).collect();
-
-            let selection = Select::with_theme(&ColorfulTheme::default())
-                .with_prompt("Pick your task")
-                .default(0)
-                .items(&friendly_task_names[..])
-                .interact()
-                .unwrap();
-
-            let task = tasks[selection].clone();
-            
-            Ok(task)
+            let tasks = describe_result.tasks.unwrap_or_default();
+            Ok(tasks.into_iter().map(Task::from).collect())
         }
     }
 }
 
+/// Prompts the user to pick one of `tasks` (prefixing each entry with its owning cluster's name),
+/// unless there's exactly one, in which case it's returned directly without prompting.
+fn select_task(mut tasks: Vec<Task>) -> Result<Task> {
+    if tasks.is_empty() {
+        bail!("No tasks found");
+    }
+    if tasks.len() == 1 {
+        return Ok(tasks.remove(0));
+    }
+
+    tasks.sort();
+    let friendly_task_names: Vec<String> = tasks.iter().map(task::Task::friendly_output).collect();
+
+    let selection = Select::with_theme(&ColorfulTheme::default())
+        .with_prompt("Pick your task")
+        .default(0)
+        .items(&friendly_task_names[..])
+        .interact()
+        .unwrap();
+
+    Ok(tasks.remove(selection))
+}
+
 fn choose_container(task: &Task, container_name: Option<String>) -> Result<Container> {
     match container_name {
         Some(name) => {
@@ -187,13 +387,70 @@ fn choose_container(task: &Task, container_name: Option<String>) -> Result<Conta
     }
 }
 
-fn execute_bash_container(cluster_arn: &str, task: &Task, container: &Container) -> Result<()> {
-    Exec::shell(format!("aws ecs execute-command --cluster {} --task {} --container {} --command \"/usr/bin/env bash\" --interactive", cluster_arn, task.arn, container.name)).join()?;
-    Ok(())
+/// Shells for when no explicit `--command` is given, in order of preference. Only the first is
+/// launched; the later entries are not tried automatically.
+const SHELL_FALLBACKS: &[&str] = &["/usr/bin/env bash", "/bin/sh"];
+
+/// Runs `command` in `container` via ECS Exec. When `command` is `None`, launches the first of
+/// `SHELL_FALLBACKS` (interactively unless `interactive` is false). No check is made beforehand
+/// that the shell exists in the image; if `/usr/bin/env bash` isn't present the user sees the
+/// container's own "not found" error and can re-run with `--command "/bin/sh"`.
+fn execute_in_container(cluster_arn: &str, task: &Task, container: &Container, command: Option<&str>, interactive: bool) -> Result<ExitStatus> {
+    let command = command.unwrap_or(SHELL_FALLBACKS[0]);
+    run_execute_command(cluster_arn, task, container, command, interactive)
+}
+
+fn run_execute_command(cluster_arn: &str, task: &Task, container: &Container, command: &str, interactive: bool) -> Result<ExitStatus> {
+    let mut exec = Exec::cmd("aws").args(&[
+        "ecs", "execute-command",
+        "--cluster", cluster_arn,
+        "--task", &task.arn,
+        "--container", &container.name,
+        "--command", command,
+    ]);
+    if interactive {
+        exec = exec.arg("--interactive");
+    }
+    exec.join().context("Failed to run aws ecs execute-command")
 }
 
-fn confirm_dependencies() -> Result<()> {
-    which("aws-vault").map_err(|_| anyhow!("Failed to find aws-vault. Is it installed and in your PATH?"))?;
-    which("aws").map_err(|_| anyhow!("Failed to find the AWS CLI. Is it installed and in your PATH?"))?;
+/// Checks for `aws-vault`/the AWS CLI only when the command being run actually needs them, so the
+/// native credential path (the whole point of `--use-aws-vault` being opt-in) doesn't force users
+/// without `aws-vault` installed to install it just to run e.g. `task-definitions list`.
+fn confirm_dependencies(command: &cli::Commands) -> Result<()> {
+    let (require_aws_vault, require_aws_cli) = match command {
+        cli::Commands::Login { .. } => (true, false),
+        cli::Commands::Execute { use_aws_vault, .. } => (*use_aws_vault, true),
+        cli::Commands::Logs { use_aws_vault, .. } => (*use_aws_vault, false),
+        cli::Commands::TaskDefinitions { command } => match command {
+            cli::TaskDefinitionCommands::List { use_aws_vault, .. }
+            | cli::TaskDefinitionCommands::Describe { use_aws_vault, .. }
+            | cli::TaskDefinitionCommands::Deregister { use_aws_vault, .. } => (*use_aws_vault, false),
+        },
+        cli::Commands::Config { .. } => (false, false),
+    };
+
+    if require_aws_vault {
+        which("aws-vault").map_err(|_| anyhow!("Failed to find aws-vault. Is it installed and in your PATH?"))?;
+    }
+    if require_aws_cli {
+        which("aws").map_err(|_| anyhow!("Failed to find the AWS CLI. Is it installed and in your PATH?"))?;
+    }
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_start_time_parses_rfc3339() {
+        let millis = parse_start_time("2023-01-01T00:00:00Z").unwrap();
+        assert_eq!(millis, 1_672_531_200_000);
+    }
+
+    #[test]
+    fn parse_start_time_rejects_non_rfc3339() {
+        assert!(parse_start_time("not a timestamp").is_err());
+    }
+}
@@ -9,6 +9,8 @@ use strum::{EnumString, Display};
 pub struct Task {
     pub name: String,
     pub arn: String,
+    pub task_definition_arn: String,
+    pub cluster_arn: String,
     pub containers: Vec<Container>,
     pub status: TaskStatus
 }
@@ -27,6 +29,8 @@ impl Ord for Task {
 }
 
 impl Task {
+    /// Friendly one-line summary for the task picker, prefixed with the owning cluster's name so
+    /// tasks from different clusters can be told apart in a merged, all-clusters listing.
     pub fn friendly_output(&self) -> String {
         let mut containers = String::from("");
         let container_count = self.containers.len();
@@ -36,18 +40,27 @@ impl Task {
                 containers.push_str(", ");
             }
         }
-        format!("{}{} ({}) [{}]", self.name, self.status.pretty_status(), self.arn, containers )
+        let cluster_name = self.cluster_arn.split(":cluster/").nth(1).unwrap_or(&self.cluster_arn);
+        format!("{} :: {}{} ({}) [{}]", cluster_name, self.name, self.status.pretty_status(), self.arn, containers )
     }
 }
 
 
 impl From<rusoto_ecs::Task> for Task {
     fn from(item: rusoto_ecs::Task) -> Self {
-        let name = item.task_definition_arn.as_ref().expect("Failed to get task arn from task").split(":task-definition/").nth(1).unwrap().split(":").nth(0).unwrap().to_owned();
+        let task_definition_arn = item.task_definition_arn.expect("Failed to get task definition arn from task");
+        let name = task_definition_arn.split(":task-definition/").nth(1).unwrap().split(":").nth(0).unwrap().to_owned();
         let containers = item.containers.expect("Failed to identify containers on task")
             .into_iter()
             .map(|c|Container { arn: c.container_arn.expect("Container had no ARN"), name: c.name.unwrap(), status: c.last_status.unwrap(), }).collect();
-        Task { name, arn: item.task_arn.unwrap(), containers, status: TaskStatus::from_str(&item.last_status.unwrap()).unwrap()}
+        Task {
+            name,
+            arn: item.task_arn.unwrap(),
+            task_definition_arn,
+            cluster_arn: item.cluster_arn.expect("Failed to get cluster arn from task"),
+            containers,
+            status: TaskStatus::from_str(&item.last_status.unwrap()).unwrap()
+        }
     }
 }
 
@@ -0,0 +1,113 @@
+use anyhow::{bail, Context, Result};
+use dialoguer::{theme::ColorfulTheme, Confirm, Select};
+use rusoto_ecs::{
+    DeregisterTaskDefinitionRequest, DescribeTaskDefinitionRequest, Ecs, EcsClient,
+    ListTaskDefinitionsRequest,
+};
+
+use crate::cli::OutputFormat;
+
+/// Lists task definition ARNs, optionally filtered by family prefix, in `ACTIVE` or `INACTIVE` status.
+/// Follows `next_token` until `ListTaskDefinitions` stops returning one, since the API paginates at
+/// ~100 results per page. Returns an empty `Vec` (not an error) when there are no matches.
+pub async fn list(client: &EcsClient, family_prefix: Option<String>, inactive: bool) -> Result<Vec<String>> {
+    let status = if inactive { "INACTIVE" } else { "ACTIVE" };
+    let mut arns = Vec::new();
+    let mut next_token = None;
+
+    loop {
+        let request = ListTaskDefinitionsRequest {
+            family_prefix: family_prefix.clone(),
+            status: Some(status.to_owned()),
+            next_token,
+            ..ListTaskDefinitionsRequest::default()
+        };
+        let response = client.list_task_definitions(request).await.context("Failed to list task definitions")?;
+        arns.extend(response.task_definition_arns.unwrap_or_default());
+
+        next_token = response.next_token;
+        if next_token.is_none() {
+            break;
+        }
+    }
+
+    arns.sort();
+    Ok(arns)
+}
+
+/// Renders a task definition ARN as `family:revision`, dropping the account/region prefix.
+pub fn friendly_name(arn: &str) -> String {
+    arn.split(":task-definition/").nth(1).unwrap_or(arn).to_owned()
+}
+
+/// Resolves which task definition to act on: the value passed on the command line, or an
+/// interactive `Select` over the matching `ACTIVE` task definitions when none was given.
+pub async fn choose_task_definition(client: &EcsClient, task_definition: Option<String>) -> Result<String> {
+    match task_definition {
+        Some(task_definition) => Ok(task_definition),
+        None => {
+            let arns = list(client, None, false).await?;
+            if arns.is_empty() {
+                bail!("No ACTIVE task definitions found");
+            }
+            let friendly_names: Vec<String> = arns.iter().map(|arn| friendly_name(arn)).collect();
+
+            let selection = Select::with_theme(&ColorfulTheme::default())
+                .with_prompt("Pick your task definition")
+                .default(0)
+                .items(&friendly_names[..])
+                .interact()
+                .unwrap();
+
+            Ok(arns[selection].clone())
+        }
+    }
+}
+
+/// Dumps a task definition as YAML or JSON.
+pub async fn describe(client: &EcsClient, task_definition: &str, format: &OutputFormat) -> Result<String> {
+    let request = DescribeTaskDefinitionRequest {
+        task_definition: task_definition.to_owned(),
+        ..DescribeTaskDefinitionRequest::default()
+    };
+    let response = client.describe_task_definition(request).await.context("Failed to describe task definition")?;
+    let task_definition = response.task_definition.context("No task definition returned")?;
+
+    match format {
+        OutputFormat::Yaml => serde_yaml::to_string(&task_definition).context("Failed to render task definition as YAML"),
+        OutputFormat::Json => serde_json::to_string_pretty(&task_definition).context("Failed to render task definition as JSON"),
+    }
+}
+
+/// Deregisters a task definition after an interactive confirmation prompt.
+pub async fn deregister(client: &EcsClient, task_definition: &str) -> Result<()> {
+    let confirmed = Confirm::with_theme(&ColorfulTheme::default())
+        .with_prompt(format!("Deregister task definition '{}'?", task_definition))
+        .default(false)
+        .interact()
+        .unwrap();
+
+    if !confirmed {
+        return Ok(());
+    }
+
+    let request = DeregisterTaskDefinitionRequest { task_definition: task_definition.to_owned() };
+    client.deregister_task_definition(request).await.context("Failed to deregister task definition")?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn friendly_name_strips_account_and_region_prefix() {
+        let arn = "arn:aws:ecs:us-east-1:123456789012:task-definition/my-family:7";
+        assert_eq!(friendly_name(arn), "my-family:7");
+    }
+
+    #[test]
+    fn friendly_name_falls_back_to_input_when_not_an_arn() {
+        assert_eq!(friendly_name("my-family:7"), "my-family:7");
+    }
+}